@@ -0,0 +1,12 @@
+//! Python exception hierarchy for `yacedar`.
+
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+
+create_exception!(yacedar, YacedarError, PyException);
+create_exception!(yacedar, PolicyParseError, YacedarError);
+create_exception!(yacedar, EntityFormatError, YacedarError);
+create_exception!(yacedar, ContextError, YacedarError);
+create_exception!(yacedar, EntityUidError, YacedarError);
+create_exception!(yacedar, SchemaError, YacedarError);
+create_exception!(yacedar, PolicyLinkError, YacedarError);
@@ -1,8 +1,14 @@
+use std::collections::HashMap;
 use std::str::FromStr;
 
 use pyo3::{prelude::*, types::{PyDict, PyList}};
 use cedar_policy as cedar;
+use pythonize::depythonize_bound;
+use rayon::prelude::*;
+use serde_json::Value;
 
+mod error;
+use error::{ContextError, EntityFormatError, EntityUidError, PolicyLinkError, PolicyParseError, SchemaError};
 
 #[pyclass]
 struct EntityUid(cedar::EntityUid);
@@ -10,10 +16,12 @@ struct EntityUid(cedar::EntityUid);
 #[pymethods]
 impl EntityUid {
     #[new]
-    fn new(type_name: &str, name: &str) -> Self {
-        let type_name = cedar::EntityTypeName::from_str(type_name).expect("invalid type_name");
-        let name = cedar::EntityId::from_str(name).expect("invalid id");
-        Self(cedar::EntityUid::from_type_name_and_id(type_name, name))
+    fn new(type_name: &str, name: &str) -> PyResult<Self> {
+        let type_name = cedar::EntityTypeName::from_str(type_name)
+            .map_err(|e| EntityUidError::new_err(e.to_string()))?;
+        let name = cedar::EntityId::from_str(name)
+            .map_err(|e| EntityUidError::new_err(e.to_string()))?;
+        Ok(Self(cedar::EntityUid::from_type_name_and_id(type_name, name)))
     }
 }
 
@@ -23,14 +31,19 @@ struct Context(cedar::Context);
 #[pymethods]
 impl Context {
     #[new]
-    fn new(value: &PyDict, py: Python) -> Self {
-        let json = py.import("json").expect("failed to import json");
-        let json_str = json
-            .call_method1("dumps", (value,))
-            .expect("failed to dump json")
-            .extract::<String>()
-            .expect("failed to extract json");
-        Self(cedar::Context::from_json_str(&json_str, None).expect("invalid context"))
+    #[pyo3(signature = (value, schema=None, action=None))]
+    fn new(value: &Bound<'_, PyDict>, schema: Option<&Schema>, action: Option<&EntityUid>) -> PyResult<Self> {
+        let value = depythonize_bound::<Value>(value.clone().into_any())
+            .map_err(|e| ContextError::new_err(e.to_string()))?;
+        let schema = match (schema, action) {
+            (Some(s), Some(a)) => Some((&s.0, &a.0)),
+            (None, None) => None,
+            (Some(_), None) => return Err(ContextError::new_err("schema requires action")),
+            (None, Some(_)) => return Err(ContextError::new_err("action requires schema")),
+        };
+        let context = cedar::Context::from_json_value(value, schema)
+            .map_err(|e| ContextError::new_err(e.to_string()))?;
+        Ok(Self(context))
     }
 }
 
@@ -56,8 +69,39 @@ struct PolicySet(cedar::PolicySet);
 #[pymethods]
 impl PolicySet {
     #[new]
-    fn new(policies_str: &str) -> Self {
-        Self(cedar::PolicySet::from_str(policies_str).expect("invalid policies"))
+    fn new(policies_str: &str) -> PyResult<Self> {
+        let policy_set = cedar::PolicySet::from_str(policies_str)
+            .map_err(|e| PolicyParseError::new_err(e.to_string()))?;
+        Ok(Self(policy_set))
+    }
+
+    /// IDs of the policy templates in this set.
+    fn templates(&self) -> Vec<String> {
+        self.0.templates().map(|t| t.id().to_string()).collect()
+    }
+
+    /// Instantiate `template_id` as a concrete policy named `link_id`, filling in its
+    /// slots (`"?principal"`, `"?resource"`) with the given `EntityUid`s.
+    fn link(&mut self, template_id: &str, link_id: &str, values: &Bound<'_, PyDict>) -> PyResult<()> {
+        let mut slot_values = HashMap::new();
+        for (slot, euid) in values.iter() {
+            let slot: String = slot.extract()?;
+            let slot_id = match slot.as_str() {
+                "?principal" => cedar::SlotId::principal(),
+                "?resource" => cedar::SlotId::resource(),
+                _ => return Err(PolicyLinkError::new_err(format!("unknown slot: {slot}"))),
+            };
+            let euid = euid.extract::<PyRef<EntityUid>>()?.0.clone();
+            slot_values.insert(slot_id, euid);
+        }
+
+        self.0
+            .link(
+                cedar::PolicyId::new(template_id),
+                cedar::PolicyId::new(link_id),
+                slot_values,
+            )
+            .map_err(|e| PolicyLinkError::new_err(e.to_string()))
     }
 }
 
@@ -67,14 +111,75 @@ struct Entities(cedar::Entities);
 #[pymethods]
 impl Entities {
     #[new]
-    fn new(value: &PyList, py: Python) -> Self {
-        let json = py.import("json").expect("failed to import json");
-        let json_str = json
-            .call_method1("dumps", (value,))
-            .expect("failed to dump json")
-            .extract::<String>()
-            .expect("failed to extract json");
-        Self(cedar::Entities::from_json_str(&json_str, None).expect("invalid entities"))
+    #[pyo3(signature = (value, schema=None))]
+    fn new(value: &Bound<'_, PyList>, schema: Option<&Schema>) -> PyResult<Self> {
+        let value = depythonize_bound::<Value>(value.clone().into_any())
+            .map_err(|e| EntityFormatError::new_err(e.to_string()))?;
+        let entities = cedar::Entities::from_json_value(value, schema.map(|s| &s.0))
+            .map_err(|e| EntityFormatError::new_err(e.to_string()))?;
+        Ok(Self(entities))
+    }
+}
+
+#[pyclass]
+struct Schema(cedar::Schema);
+
+#[pymethods]
+impl Schema {
+    #[new]
+    fn new(value: &Bound<'_, PyDict>) -> PyResult<Self> {
+        let value = depythonize_bound::<Value>(value.clone().into_any())
+            .map_err(|e| SchemaError::new_err(e.to_string()))?;
+        let schema = cedar::Schema::from_json_value(value)
+            .map_err(|e| SchemaError::new_err(e.to_string()))?;
+        Ok(Self(schema))
+    }
+}
+
+#[pyclass]
+#[derive(Clone, Copy)]
+enum ValidationMode {
+    Strict,
+    Permissive,
+}
+
+#[pyclass]
+struct ValidationResult(cedar::ValidationResult);
+
+#[pymethods]
+impl ValidationResult {
+    #[getter]
+    fn validation_passed(&self) -> bool {
+        self.0.validation_passed()
+    }
+
+    #[getter]
+    fn errors(&self) -> Vec<String> {
+        self.0.validation_errors().map(|e| e.to_string()).collect()
+    }
+
+    #[getter]
+    fn warnings(&self) -> Vec<String> {
+        self.0.validation_warnings().map(|w| w.to_string()).collect()
+    }
+}
+
+#[pyclass]
+struct Validator(cedar::Validator);
+
+#[pymethods]
+impl Validator {
+    #[new]
+    fn new(schema: &Schema) -> Self {
+        Self(cedar::Validator::new(schema.0.clone()))
+    }
+
+    fn validate(&self, policy_set: &PolicySet, mode: ValidationMode) -> ValidationResult {
+        let mode = match mode {
+            ValidationMode::Strict => cedar::ValidationMode::Strict,
+            ValidationMode::Permissive => cedar::ValidationMode::Permissive,
+        };
+        ValidationResult(self.0.validate(&policy_set.0, mode))
     }
 }
 
@@ -88,9 +193,33 @@ impl Authorizer {
         Self(cedar::Authorizer::new())
     }
 
-    fn is_authorized(&self, request: &Request, policy_set: &PolicySet, entities: &Entities) -> Response {
+    fn is_authorized(&self, request: &Request, policy_set: &PolicySet, entities: &Entities) -> PyResult<Response> {
         let response = self.0.is_authorized(&request.0, &policy_set.0, &entities.0);
-        Response(response)
+        Ok(Response(response))
+    }
+
+    /// Evaluate many requests against the same `policy_set`/`entities`, releasing the
+    /// GIL and fanning the evaluation out across a rayon thread pool.
+    fn is_authorized_batch(
+        &self,
+        py: Python,
+        requests: Vec<PyRef<Request>>,
+        policy_set: &PolicySet,
+        entities: &Entities,
+    ) -> PyResult<Vec<Response>> {
+        let requests: Vec<cedar::Request> = requests.iter().map(|r| r.0.clone()).collect();
+        let authorizer = &self.0;
+        let policy_set = &policy_set.0;
+        let entities = &entities.0;
+
+        let responses = py.allow_threads(|| {
+            requests
+                .par_iter()
+                .map(|request| authorizer.is_authorized(request, policy_set, entities))
+                .collect::<Vec<_>>()
+        });
+
+        Ok(responses.into_iter().map(Response).collect())
     }
 }
 
@@ -111,6 +240,18 @@ impl Response {
     fn allowed(&self) -> bool {
         return self.0.decision() == cedar::Decision::Allow
     }
+
+    /// Policy IDs that contributed to the decision.
+    #[getter]
+    fn reason(&self) -> Vec<String> {
+        self.0.diagnostics().reason().map(|id| id.to_string()).collect()
+    }
+
+    /// Errors encountered while evaluating the policies.
+    #[getter]
+    fn errors(&self) -> Vec<String> {
+        self.0.diagnostics().errors().map(|e| e.to_string()).collect()
+    }
 }
 
 #[pyclass]
@@ -121,7 +262,7 @@ enum Decision {
 
 /// A Python module implemented in Rust.
 #[pymodule]
-fn yacedar(_py: Python, m: &PyModule) -> PyResult<()> {
+fn yacedar(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<EntityUid>()?;
     m.add_class::<Context>()?;
     m.add_class::<Request>()?;
@@ -130,5 +271,258 @@ fn yacedar(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<Authorizer>()?;
     m.add_class::<Response>()?;
     m.add_class::<Decision>()?;
+    m.add_class::<Schema>()?;
+    m.add_class::<Validator>()?;
+    m.add_class::<ValidationResult>()?;
+    m.add_class::<ValidationMode>()?;
+
+    let py = m.py();
+    m.add("YacedarError", py.get_type_bound::<error::YacedarError>())?;
+    m.add("PolicyParseError", py.get_type_bound::<error::PolicyParseError>())?;
+    m.add("EntityFormatError", py.get_type_bound::<error::EntityFormatError>())?;
+    m.add("ContextError", py.get_type_bound::<error::ContextError>())?;
+    m.add("EntityUidError", py.get_type_bound::<error::EntityUidError>())?;
+    m.add("SchemaError", py.get_type_bound::<error::SchemaError>())?;
+    m.add("PolicyLinkError", py.get_type_bound::<error::PolicyLinkError>())?;
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA_JSON: &str = r#"{
+        "": {
+            "entityTypes": {
+                "User": {},
+                "Resource": {}
+            },
+            "actions": {
+                "view": {
+                    "appliesTo": {
+                        "principalTypes": ["User"],
+                        "resourceTypes": ["Resource"],
+                        "context": {
+                            "type": "Record",
+                            "attributes": {
+                                "ip": { "type": "String" }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }"#;
+
+    fn py_schema_dict<'py>(py: Python<'py>) -> Bound<'py, PyDict> {
+        py.eval_bound(SCHEMA_JSON, None, None)
+            .unwrap()
+            .downcast_into::<PyDict>()
+            .unwrap()
+    }
+
+    #[test]
+    fn schema_parses_valid_json() {
+        Python::with_gil(|py| {
+            let dict = py_schema_dict(py);
+            assert!(Schema::new(&dict).is_ok());
+        });
+    }
+
+    #[test]
+    fn schema_rejects_malformed_json() {
+        Python::with_gil(|py| {
+            let dict = py.eval_bound("{'not': 'a schema'}", None, None)
+                .unwrap()
+                .downcast_into::<PyDict>()
+                .unwrap();
+            assert!(Schema::new(&dict).is_err());
+        });
+    }
+
+    #[test]
+    fn validator_passes_for_compliant_policy() {
+        Python::with_gil(|py| {
+            let schema = Schema::new(&py_schema_dict(py)).unwrap();
+            let policy_set =
+                PolicySet::new(r#"permit(principal, action == Action::"view", resource);"#)
+                    .unwrap();
+            let validator = Validator::new(&schema);
+            let result = validator.validate(&policy_set, ValidationMode::Strict);
+            assert!(result.validation_passed());
+            assert!(result.errors().is_empty());
+        });
+    }
+
+    #[test]
+    fn validator_flags_policy_with_unknown_action() {
+        Python::with_gil(|py| {
+            let schema = Schema::new(&py_schema_dict(py)).unwrap();
+            let policy_set =
+                PolicySet::new(r#"permit(principal, action == Action::"delete", resource);"#)
+                    .unwrap();
+            let validator = Validator::new(&schema);
+            let result = validator.validate(&policy_set, ValidationMode::Strict);
+            assert!(!result.validation_passed());
+            assert!(!result.errors().is_empty());
+        });
+    }
+
+    #[test]
+    fn context_with_schema_and_action_is_validated() {
+        Python::with_gil(|py| {
+            let schema = Schema::new(&py_schema_dict(py)).unwrap();
+            let action = EntityUid::new("Action", "view").unwrap();
+            let value = py.eval_bound(r#"{"ip": "127.0.0.1"}"#, None, None)
+                .unwrap()
+                .downcast_into::<PyDict>()
+                .unwrap();
+            assert!(Context::new(&value, Some(&schema), Some(&action)).is_ok());
+        });
+    }
+
+    #[test]
+    fn context_with_schema_but_no_action_errors() {
+        Python::with_gil(|py| {
+            let schema = Schema::new(&py_schema_dict(py)).unwrap();
+            let value = py.eval_bound(r#"{"ip": "127.0.0.1"}"#, None, None)
+                .unwrap()
+                .downcast_into::<PyDict>()
+                .unwrap();
+            let err = Context::new(&value, Some(&schema), None).unwrap_err();
+            assert!(err.to_string().contains("schema requires action"));
+        });
+    }
+
+    #[test]
+    fn context_with_action_but_no_schema_errors() {
+        Python::with_gil(|py| {
+            let action = EntityUid::new("Action", "view").unwrap();
+            let value = py.eval_bound(r#"{"ip": "127.0.0.1"}"#, None, None)
+                .unwrap()
+                .downcast_into::<PyDict>()
+                .unwrap();
+            let err = Context::new(&value, None, Some(&action)).unwrap_err();
+            assert!(err.to_string().contains("action requires schema"));
+        });
+    }
+
+    #[test]
+    fn context_rejects_value_mismatching_schema() {
+        Python::with_gil(|py| {
+            let schema = Schema::new(&py_schema_dict(py)).unwrap();
+            let action = EntityUid::new("Action", "view").unwrap();
+            let value = py.eval_bound(r#"{"ip": 127}"#, None, None)
+                .unwrap()
+                .downcast_into::<PyDict>()
+                .unwrap();
+            assert!(Context::new(&value, Some(&schema), Some(&action)).is_err());
+        });
+    }
+
+    #[test]
+    fn policy_set_link_happy_path() {
+        Python::with_gil(|py| {
+            let mut policy_set =
+                PolicySet::new(r#"permit(principal == ?principal, action, resource in ?resource);"#)
+                    .unwrap();
+            assert_eq!(policy_set.templates(), vec!["policy0"]);
+
+            let principal = EntityUid::new("User", "alice").unwrap();
+            let resource = EntityUid::new("Folder", "shared").unwrap();
+            let values = PyDict::new_bound(py);
+            values.set_item("?principal", Py::new(py, principal).unwrap()).unwrap();
+            values.set_item("?resource", Py::new(py, resource).unwrap()).unwrap();
+
+            assert!(policy_set.link("policy0", "link0", &values).is_ok());
+        });
+    }
+
+    #[test]
+    fn policy_set_link_rejects_unknown_slot() {
+        Python::with_gil(|py| {
+            let mut policy_set =
+                PolicySet::new(r#"permit(principal == ?principal, action, resource in ?resource);"#)
+                    .unwrap();
+
+            let principal = EntityUid::new("User", "alice").unwrap();
+            let values = PyDict::new_bound(py);
+            values.set_item("?unknown", Py::new(py, principal).unwrap()).unwrap();
+
+            let err = policy_set.link("policy0", "link0", &values).unwrap_err();
+            assert!(err.to_string().contains("unknown slot"));
+        });
+    }
+
+    #[test]
+    fn policy_set_link_rejects_unknown_template() {
+        Python::with_gil(|py| {
+            let mut policy_set = PolicySet::new("permit(principal, action, resource);").unwrap();
+            let values = PyDict::new_bound(py);
+
+            assert!(policy_set.link("no_such_template", "link0", &values).is_err());
+        });
+    }
+
+    #[test]
+    fn is_authorized_batch_matches_individual_calls() {
+        Python::with_gil(|py| {
+            let alice = EntityUid::new("User", "alice").unwrap();
+            let bob = EntityUid::new("User", "bob").unwrap();
+            let view = EntityUid::new("Action", "view").unwrap();
+            let doc = EntityUid::new("Document", "1").unwrap();
+
+            let policy_set =
+                PolicySet::new(r#"permit(principal == User::"alice", action, resource);"#)
+                    .unwrap();
+            let entities =
+                Entities::new(&PyList::empty_bound(py), None).unwrap();
+
+            let req_alice = Py::new(py, Request::new(Some(&alice), Some(&view), Some(&doc), None)).unwrap();
+            let req_bob = Py::new(py, Request::new(Some(&bob), Some(&view), Some(&doc), None)).unwrap();
+
+            let authorizer = Authorizer::new();
+            let batch = authorizer
+                .is_authorized_batch(
+                    py,
+                    vec![req_alice.borrow(py), req_bob.borrow(py)],
+                    &policy_set,
+                    &entities,
+                )
+                .unwrap();
+
+            assert_eq!(batch.len(), 2);
+            assert!(batch[0].allowed());
+            assert!(!batch[1].allowed());
+
+            let individual: Vec<bool> = [&req_alice, &req_bob]
+                .iter()
+                .map(|r| {
+                    authorizer
+                        .is_authorized(&r.borrow(py), &policy_set, &entities)
+                        .unwrap()
+                        .allowed()
+                })
+                .collect();
+            let batched: Vec<bool> = batch.iter().map(|r| r.allowed()).collect();
+            assert_eq!(batched, individual);
+        });
+    }
+
+    #[test]
+    fn is_authorized_batch_empty_list_does_not_panic() {
+        Python::with_gil(|py| {
+            let policy_set = PolicySet::new("permit(principal, action, resource);").unwrap();
+            let entities =
+                Entities::new(&PyList::empty_bound(py), None).unwrap();
+
+            let authorizer = Authorizer::new();
+            let batch = authorizer
+                .is_authorized_batch(py, vec![], &policy_set, &entities)
+                .unwrap();
+
+            assert!(batch.is_empty());
+        });
+    }
+}